@@ -21,7 +21,13 @@ from loguru import logger
 from starlette.exceptions import HTTPException as StarletteHTTPException
 
 from api.auth import PasswordAuthMiddleware
-from api.middleware import MaxBodySizeMiddleware, get_max_upload_size_bytes
+from api.middleware import (
+    REQUEST_ID_HEADER,
+    MaxBodySizeMiddleware,
+    RequestIdMiddleware,
+    add_request_id_to_log_record,
+    get_max_upload_size_bytes,
+)
 from api.routers import (
     auth,
     capabilities,
@@ -47,6 +53,8 @@ from api.routers import (
 )
 from api.routers import commands as commands_router
 from open_notebook.database.async_migrate import AsyncMigrationManager
+from open_notebook.domain.credential import Credential
+from open_notebook.domain.provider_config import ProviderConfig
 from open_notebook.exceptions import (
     AuthenticationError,
     ConfigurationError,
@@ -58,7 +66,7 @@ from open_notebook.exceptions import (
     RateLimitError,
     UnsupportedTypeException,
 )
-from open_notebook.utils.encryption import get_secret_from_env
+from open_notebook.utils.encryption import get_secret_from_env, prime_key_derivation
 
 
 def _parse_cors_origins(raw: str) -> list[str]:
@@ -108,6 +116,7 @@ def _cors_headers(request: Request) -> dict[str, str]:
     headers: dict[str, str] = {
         "Access-Control-Allow-Methods": "*",
         "Access-Control-Allow-Headers": "*",
+        "Access-Control-Expose-Headers": REQUEST_ID_HEADER,
     }
     if CORS_ALLOW_CREDENTIALS:
         headers["Access-Control-Allow-Credentials"] = "true"
@@ -207,6 +216,25 @@ async def lifespan(app: FastAPI):
         # Fail fast - don't start the API with an outdated database schema
         raise RuntimeError(f"Failed to run database migrations: {str(e)}") from e
 
+    if get_secret_from_env("OPEN_NOTEBOOK_ENCRYPTION_KEY"):
+        # Pay for the Argon2id derivation here, in a thread, instead of on the
+        # event loop of the first request that touches a credential
+        await asyncio.to_thread(prime_key_derivation)
+
+        # Upgrade credentials (and the pre-credential provider_configs record,
+        # which is kept after migration) still encrypted with the legacy
+        # SHA-256 key derivation. Non-fatal: unreadable keys are skipped and
+        # stay readable by the legacy path.
+        try:
+            upgraded = await Credential.reencrypt_legacy_api_keys()
+            upgraded += await ProviderConfig.reencrypt_legacy_api_keys()
+            if upgraded:
+                logger.info(
+                    f"Re-encrypted {upgraded} credential(s) with the current key derivation"
+                )
+        except Exception as e:
+            logger.warning(f"Could not re-encrypt legacy credentials: {str(e)}")
+
     logger.success("API initialization completed successfully")
 
     # Yield control to the application
@@ -256,8 +284,9 @@ logger.info(
 )
 app.add_middleware(MaxBodySizeMiddleware, max_body_size=MAX_UPLOAD_SIZE_BYTES)
 
-# Add CORS middleware last (so it processes first, and so it can attach
-# CORS headers to a 413 raised by MaxBodySizeMiddleware)
+# Add CORS middleware after the auth and body-size middlewares (so it wraps
+# them and can attach CORS headers to a 413 raised by MaxBodySizeMiddleware).
+# Only RequestIdMiddleware, below, runs outside it.
 #
 # allow_credentials is tied to whether CORS_ORIGINS resolves to specific
 # origins: combining allow_origins=["*"] with allow_credentials=True makes
@@ -274,6 +303,19 @@ app.add_middleware(
     allow_credentials=CORS_ALLOW_CREDENTIALS,
     allow_methods=["*"],
     allow_headers=["*"],
+    # Let the browser frontend read the id so users can report it
+    expose_headers=[REQUEST_ID_HEADER],
+)
+
+# Request ids wrap everything else, so even responses produced by the
+# middlewares above (413, 401, CORS preflight) carry X-Request-Id, and every
+# log line emitted while handling a request is prefixed with its id. Its own
+# 500 for unhandled errors bypasses CORSMiddleware, so it gets the same CORS
+# headers as the exception handlers below.
+logger.configure(patcher=add_request_id_to_log_record)
+app.add_middleware(
+    RequestIdMiddleware,
+    error_headers=lambda scope: _cors_headers(Request(scope)),
 )
 
 
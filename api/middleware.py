@@ -1,9 +1,15 @@
 import os
+import re
+import uuid
+from typing import TYPE_CHECKING, Callable, Optional
 
 from loguru import logger
-from starlette.datastructures import Headers
+from starlette.datastructures import Headers, MutableHeaders
 from starlette.types import ASGIApp, Message, Receive, Scope, Send
 
+if TYPE_CHECKING:
+    from loguru import Record
+
 # Matches the file-size guidance already documented in
 # docs/3-USER-GUIDE/adding-sources.md ("Very large files (>100MB) - Timeout").
 DEFAULT_MAX_UPLOAD_SIZE_MB = 100
@@ -120,3 +126,106 @@ async def _send_413(send: Send) -> None:
             "body": b'{"detail":"Request body exceeds the maximum allowed upload size"}',
         }
     )
+
+
+REQUEST_ID_HEADER = "X-Request-Id"
+# Client-supplied ids end up in log lines, so only a conservative charset and
+# length is accepted (no newlines or control characters to forge log entries);
+# anything else is replaced with a freshly generated id.
+_VALID_REQUEST_ID = re.compile(r"^[A-Za-z0-9._:-]{1,128}$")
+
+
+def add_request_id_to_log_record(record: "Record") -> None:
+    """Loguru patcher prefixing messages logged during a request with its id.
+
+    Patching the message (rather than the sink format) keeps whatever sinks
+    and formats are configured, and leaves log lines emitted outside a
+    request unchanged.
+    """
+    request_id = record["extra"].get("request_id")
+    if request_id:
+        record["message"] = f"[{request_id}] {record['message']}"
+
+
+class RequestIdMiddleware:
+    """
+    Raw ASGI middleware giving every HTTP request an id that users can
+    report and operators can grep for.
+
+    Reuses a well-formed incoming `X-Request-Id` (so an id set by a reverse
+    proxy carries through) or generates one. The id is exposed to handlers
+    as `request.state.request_id`, bound to loguru's context for everything
+    logged while the request is handled (see add_request_id_to_log_record),
+    and returned on every response - errors included - as `X-Request-Id`.
+
+    Unhandled exceptions are answered here with a JSON 500 carrying the id.
+    That response is sent outside CORSMiddleware, so `error_headers` lets the
+    app add the CORS headers a cross-origin client needs to read it.
+    """
+
+    def __init__(
+        self,
+        app: ASGIApp,
+        error_headers: Optional[Callable[[Scope], dict[str, str]]] = None,
+    ) -> None:
+        self.app = app
+        self.error_headers = error_headers
+
+    async def __call__(self, scope: Scope, receive: Receive, send: Send) -> None:
+        if scope["type"] != "http":
+            await self.app(scope, receive, send)
+            return
+
+        incoming = Headers(scope=scope).get(REQUEST_ID_HEADER)
+        if incoming and _VALID_REQUEST_ID.match(incoming):
+            request_id = incoming
+        else:
+            request_id = uuid.uuid4().hex
+        scope.setdefault("state", {})["request_id"] = request_id
+
+        response_started = False
+
+        async def send_wrapper(message: Message) -> None:
+            nonlocal response_started
+            if message["type"] == "http.response.start":
+                response_started = True
+                message.setdefault("headers", [])
+                MutableHeaders(scope=message)[REQUEST_ID_HEADER] = request_id
+            await send(message)
+
+        with logger.contextualize(request_id=request_id):
+            try:
+                await self.app(scope, receive, send_wrapper)
+            except Exception:
+                # Logged with the traceback inside the request context, so the
+                # stack trace itself carries the id (the server's own
+                # traceback is logged outside it)
+                logger.exception(
+                    f"Unhandled error in {scope.get('method', '?')} "
+                    f"{scope.get('path', '?')}"
+                )
+                # Otherwise Starlette's outermost ServerErrorMiddleware would
+                # answer with a bare 500 that has no id. It sees the response
+                # as started and only re-raises.
+                if not response_started:
+                    extra = self.error_headers(scope) if self.error_headers else {}
+                    await _send_500(send_wrapper, extra)
+                raise
+
+
+async def _send_500(send: Send, extra_headers: dict[str, str]) -> None:
+    headers = [(b"content-type", b"application/json")]
+    headers += [(k.lower().encode(), v.encode()) for k, v in extra_headers.items()]
+    await send(
+        {
+            "type": "http.response.start",
+            "status": 500,
+            "headers": headers,
+        }
+    )
+    await send(
+        {
+            "type": "http.response.body",
+            "body": b'{"detail":"Internal Server Error"}',
+        }
+    )
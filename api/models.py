@@ -37,7 +37,9 @@ class RecentlyViewedResponse(BaseModel):
 
 # Search models
 class SearchRequest(BaseModel):
-    query: str = Field(..., description="Search query")
+    query: str = Field(
+        ..., max_length=10000, description="Search query (max 10,000 characters)"
+    )
     type: Literal["text", "vector"] = Field("text", description="Search type")
     limit: int = Field(100, description="Maximum number of results", ge=1, le=1000)
     search_sources: bool = Field(True, description="Include sources in search")
@@ -54,7 +56,11 @@ class SearchResponse(BaseModel):
 
 
 class AskRequest(BaseModel):
-    question: str = Field(..., description="Question to ask the knowledge base")
+    question: str = Field(
+        ...,
+        max_length=10000,
+        description="Question to ask the knowledge base (max 10,000 characters)",
+    )
     strategy_model: str = Field(..., description="Model ID for query strategy")
     answer_model: str = Field(..., description="Model ID for individual answers")
     final_answer_model: str = Field(..., description="Model ID for final answer")
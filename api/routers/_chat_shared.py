@@ -19,6 +19,7 @@ from pydantic import BaseModel, Field
 
 from open_notebook.database.repository import ensure_record_id, repo_query
 from open_notebook.domain.notebook import ChatSession, Source
+from open_notebook.utils.text_utils import extract_text_content
 
 
 # Shared response models
@@ -80,14 +81,24 @@ async def get_verified_source_session(
 
 
 def extract_chat_messages(raw_messages: Iterable[Any]) -> List[ChatMessage]:
-    """Convert LangGraph/LangChain state messages into `ChatMessage` models."""
+    """Convert LangGraph/LangChain state messages into `ChatMessage` models.
+
+    Content is flattened with `extract_text_content()`: checkpoints written
+    before the graphs normalized AI output (or by OpenAI-compatible proxies
+    that answer with a list of content parts) can hold a list instead of a
+    string, which would otherwise fail `ChatMessage` validation.
+    """
     messages: List[ChatMessage] = []
     for msg in raw_messages:
         messages.append(
             ChatMessage(
                 id=getattr(msg, "id", f"msg_{len(messages)}"),
                 type=msg.type if hasattr(msg, "type") else "unknown",
-                content=msg.content if hasattr(msg, "content") else str(msg),
+                content=(
+                    extract_text_content(msg.content)
+                    if hasattr(msg, "content")
+                    else str(msg)
+                ),
                 timestamp=None,  # LangChain messages don't have timestamps by default
             )
         )
@@ -24,6 +24,7 @@ from open_notebook.exceptions import (
 )
 from open_notebook.graphs.source_chat import source_chat_graph as source_chat_graph
 from open_notebook.utils.graph_utils import get_session_message_count
+from open_notebook.utils.text_utils import extract_text_content
 
 router = APIRouter()
 
@@ -378,7 +379,11 @@ async def stream_source_chat_response(
                 if hasattr(msg, "type") and msg.type == "ai":
                     ai_event = {
                         "type": "ai_message",
-                        "content": msg.content if hasattr(msg, "content") else str(msg),
+                        "content": (
+                            extract_text_content(msg.content)
+                            if hasattr(msg, "content")
+                            else str(msg)
+                        ),
                         "timestamp": None,
                     }
                     yield f"data: {json.dumps(ai_event)}\n\n"
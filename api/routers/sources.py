@@ -1,4 +1,5 @@
 import asyncio
+import mimetypes
 import os
 from pathlib import Path
 from typing import Any, List, Optional
@@ -45,6 +46,21 @@ from open_notebook.exceptions import (
 
 router = APIRouter()
 
+# Media types a browser can render in-page without executing script. Anything
+# else (HTML, SVG, ...) is always served as an application/octet-stream
+# attachment, even when the caller asks for inline display - rendering an
+# uploaded HTML page on the API origin would hand it that origin's privileges
+# (see tests/test_upload_type_mitigations.py).
+INLINE_SAFE_MEDIA_TYPES = {
+    "application/pdf",
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+    "text/plain",
+}
+INLINE_SAFE_MEDIA_PREFIXES = ("audio/", "video/")
+
 
 async def _assert_file_supported(file_path: str) -> None:
     """Pre-flight check that content-core can actually extract this file.
@@ -821,15 +837,48 @@ async def check_source_file(source_id: str):
         raise HTTPException(status_code=500, detail="Failed to verify file")
 
 
+def _source_file_disposition(filename: str, inline: bool) -> tuple[str, str]:
+    """Pick the (media_type, disposition) pair used to serve a source file.
+
+    Only types in INLINE_SAFE_MEDIA_TYPES / INLINE_SAFE_MEDIA_PREFIXES keep
+    their detected media type (and may be shown inline). Every other file is
+    served as an application/octet-stream attachment, so the browser
+    downloads it instead of rendering it.
+    """
+    media_type, _ = mimetypes.guess_type(filename)
+    if media_type and (
+        media_type in INLINE_SAFE_MEDIA_TYPES
+        or media_type.startswith(INLINE_SAFE_MEDIA_PREFIXES)
+    ):
+        return media_type, "inline" if inline else "attachment"
+    return "application/octet-stream", "attachment"
+
+
 @router.get("/sources/{source_id}/download")
-async def download_source_file(source_id: str):
-    """Download the original file associated with an uploaded source."""
+async def download_source_file(
+    source_id: str,
+    inline: bool = Query(
+        False,
+        description=(
+            "Serve the file for in-browser display (PDF, images, audio, video, "
+            "plain text) instead of as a download"
+        ),
+    ),
+):
+    """Download or stream the original file associated with an uploaded source.
+
+    Supports HTTP Range requests (206 Partial Content), so PDF viewers and
+    media players can fetch only the part of the file they need.
+    """
     try:
         resolved_path, filename = await _resolve_source_file(source_id)
+        media_type, disposition = _source_file_disposition(filename, inline)
         return FileResponse(
             path=resolved_path,
             filename=filename,
-            media_type="application/octet-stream",
+            media_type=media_type,
+            content_disposition_type=disposition,
+            headers={"X-Content-Type-Options": "nosniff"},
         )
     except HTTPException:
         raise
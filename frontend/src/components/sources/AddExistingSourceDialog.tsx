@@ -17,6 +17,7 @@ import { Checkbox } from '@/components/ui/checkbox'
 import { Badge } from '@/components/ui/badge'
 import { ScrollArea } from '@/components/ui/scroll-area'
 import { searchApi } from '@/lib/api/search'
+import { MAX_SEARCH_INPUT_LENGTH } from '@/lib/types/search'
 import { sourcesApi } from '@/lib/api/sources'
 import { useSources, useAddSourcesToNotebook } from '@/lib/hooks/use-sources'
 import { SourceListResponse } from '@/lib/types/api'
@@ -201,6 +202,7 @@ export function AddExistingSourceDialog({
               placeholder={t('sources.searchPlaceholder')}
               value={searchQuery}
               onChange={(e) => setSearchQuery(e.target.value)}
+              maxLength={MAX_SEARCH_INPUT_LENGTH}
               className="pl-10"
             />
             {isSearching && (
@@ -1,4 +1,9 @@
 // Search types
+
+// Mirrors max_length on SearchRequest.query / AskRequest.question in
+// api/models.py - the API rejects anything longer with a 422.
+export const MAX_SEARCH_INPUT_LENGTH = 10000
+
 export interface SearchRequest {
   query: string
   type: 'text' | 'vector'
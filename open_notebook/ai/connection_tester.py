@@ -22,6 +22,7 @@ from esperanto.common_types import ChatCompletion
 from loguru import logger
 
 from open_notebook.ai.provider_registry import PROVIDERS
+from open_notebook.utils.text_utils import extract_text_content
 from open_notebook.utils.url_validation import prepare_pinned_http_target
 
 
@@ -504,7 +505,8 @@ async def test_individual_model(model) -> Tuple[bool, str]:
             if not isinstance(response, ChatCompletion):
                 # Non-streaming call; a streaming response would be a bug upstream.
                 return True, "Connection successful (streaming response)"
-            text = response.content[:100] if response.content else "(empty response)"
+            content = extract_text_content(response.content)
+            text = content[:100] if content else "(empty response)"
             return True, f"Response: {text}"
 
         elif model.type == "embedding":
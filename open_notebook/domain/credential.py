@@ -22,7 +22,11 @@ from pydantic import SecretStr, model_validator
 
 from open_notebook.database.repository import ensure_record_id, repo_query
 from open_notebook.domain.base import ObjectModel
-from open_notebook.utils.encryption import decrypt_value, encrypt_value
+from open_notebook.utils.encryption import (
+    decrypt_value,
+    encrypt_value,
+    needs_reencryption,
+)
 
 
 class Credential(ObjectModel):
@@ -217,6 +221,37 @@ class Credential(ObjectModel):
                     )
         return credentials
 
+    @classmethod
+    async def reencrypt_legacy_api_keys(cls) -> int:
+        """Re-encrypt api_key values still stored with the legacy key derivation.
+
+        Rows whose key cannot be decrypted (e.g. the encryption key changed)
+        are left untouched and logged, so a misconfiguration never destroys
+        data. Returns the number of rows upgraded.
+        """
+        rows = await repo_query(f"SELECT id, api_key FROM {cls.table_name}", {})
+        upgraded = 0
+        for row in rows:
+            stored = row.get("api_key")
+            if not isinstance(stored, str) or not needs_reencryption(stored):
+                continue
+            try:
+                plaintext = decrypt_value(stored)
+            except ValueError as e:
+                logger.warning(
+                    f"Skipping re-encryption of credential {row.get('id', 'unknown')}: {e}"
+                )
+                continue
+            await repo_query(
+                "UPDATE $id SET api_key = $api_key",
+                {
+                    "id": ensure_record_id(row["id"]),
+                    "api_key": encrypt_value(plaintext),
+                },
+            )
+            upgraded += 1
+        return upgraded
+
     async def get_linked_models(self) -> list:
         """Get all models linked to this credential."""
         if not self.id:
@@ -12,11 +12,16 @@ is set. If not set, keys are stored as plain text with a warning logged.
 from datetime import datetime
 from typing import Any, ClassVar, Dict, List, Optional
 
+from loguru import logger
 from pydantic import Field, SecretStr
 
 from open_notebook.database.repository import ensure_record_id, repo_query, repo_upsert
 from open_notebook.domain.base import RecordModel
-from open_notebook.utils.encryption import decrypt_value, encrypt_value
+from open_notebook.utils.encryption import (
+    decrypt_value,
+    encrypt_value,
+    needs_reencryption,
+)
 
 
 class ProviderCredential:
@@ -277,6 +282,52 @@ class ProviderConfig(RecordModel):
 
         return instance
 
+    @classmethod
+    async def reencrypt_legacy_api_keys(cls) -> int:
+        """Re-encrypt api_key values still stored with the legacy key derivation.
+
+        The record is kept after migrate_from_provider_config() copies it into
+        credential rows, so its keys must be upgraded too - otherwise a
+        database dump still holds tokens under the cheap SHA-256 derivation
+        of the same passphrase. Like Credential.reencrypt_legacy_api_keys(),
+        keys that cannot be decrypted are left untouched and logged. Returns
+        the number of keys upgraded.
+        """
+        record_id = ensure_record_id(cls.record_id)
+        result = await repo_query(
+            "SELECT * FROM ONLY $record_id", {"record_id": record_id}
+        )
+        data = result[0] if isinstance(result, list) and result else result
+        creds_data = data.get("credentials") if isinstance(data, dict) else None
+        if not isinstance(creds_data, dict):
+            return 0
+
+        upgraded = 0
+        for provider, provider_creds in creds_data.items():
+            if not isinstance(provider_creds, list):
+                continue
+            for cred_data in provider_creds:
+                stored = cred_data.get("api_key")
+                if not isinstance(stored, str) or not needs_reencryption(stored):
+                    continue
+                try:
+                    plaintext = decrypt_value(stored)
+                except ValueError as e:
+                    logger.warning(
+                        f"Skipping re-encryption of provider config "
+                        f"{provider}/{cred_data.get('name', 'unknown')}: {e}"
+                    )
+                    continue
+                cred_data["api_key"] = encrypt_value(plaintext)
+                upgraded += 1
+
+        if upgraded:
+            await repo_query(
+                "UPDATE $record_id SET credentials = $credentials",
+                {"record_id": record_id, "credentials": creds_data},
+            )
+        return upgraded
+
     def get_default_config(self, provider: str) -> Optional[ProviderCredential]:
         """
         Get the default configuration for a provider.
@@ -4,9 +4,17 @@ Field-level encryption for sensitive data using API keys.
 This module provides encryption/decryption for API keys stored in the database.
 Fernet uses AES-128-CBC with HMAC-SHA256 for authenticated encryption.
 
-OPEN_NOTEBOOK_ENCRYPTION_KEY accepts **any string**. A Fernet key is derived
-from it via SHA-256, so users can set a simple passphrase like
-``OPEN_NOTEBOOK_ENCRYPTION_KEY=my-secret`` and it will work.
+OPEN_NOTEBOOK_ENCRYPTION_KEY accepts **any string**, so users can set a simple
+passphrase like ``OPEN_NOTEBOOK_ENCRYPTION_KEY=my-secret`` and it will work.
+
+Key derivation is versioned by a prefix on the stored value:
+
+- ``v2:<salt>:<token>`` (current): a master key is stretched from the
+  passphrase with Argon2id once per process, then HKDF-SHA256 derives a
+  per-value Fernet key using the random salt stored alongside the token.
+- no prefix (legacy): the Fernet key is a single SHA-256 of the passphrase.
+  Still decrypted so existing databases keep working; ``encrypt_value``
+  never writes it, and ``needs_reencryption`` identifies values to upgrade.
 
 Usage:
     # Encrypt before storing
@@ -17,14 +25,34 @@ Usage:
 """
 
 import base64
+import binascii
 import hashlib
 import os
+from functools import lru_cache
 from pathlib import Path
 from typing import Optional
 
 from cryptography.fernet import Fernet, InvalidToken
+from cryptography.hazmat.primitives import hashes
+from cryptography.hazmat.primitives.kdf.argon2 import Argon2id
+from cryptography.hazmat.primitives.kdf.hkdf import HKDF
 from loguru import logger
 
+CIPHERTEXT_V2_PREFIX = "v2:"
+
+# Argon2id cost for stretching the passphrase (RFC 9106 second recommended
+# option). Paid once per process - the master key is cached.
+ARGON2_ITERATIONS = 3
+ARGON2_LANES = 4
+ARGON2_MEMORY_COST_KIB = 64 * 1024
+# The passphrase is the only thing operators configure, so there is nowhere
+# to keep a per-install random salt. A fixed application salt still forces
+# every guess through Argon2id; per-value randomness comes from the HKDF salt
+# stored in each ciphertext.
+ARGON2_SALT = b"open-notebook/credential-encryption/v2"
+HKDF_INFO = b"open-notebook credential v2"
+HKDF_SALT_BYTES = 16
+
 
 def get_secret_from_env(var_name: str) -> Optional[str]:
     """
@@ -103,10 +131,11 @@ def _get_encryption_key() -> str:
 
 def _ensure_fernet_key(key: str) -> str:
     """
-    Derive a valid Fernet key from an arbitrary string via SHA-256.
+    Derive the legacy Fernet key from an arbitrary string via SHA-256.
 
-    Any string is accepted as input. The key is derived by hashing it with
-    SHA-256 and encoding the result as URL-safe base64.
+    Only used to read values written before the Argon2id/HKDF derivation;
+    a single unsalted hash is cheap to brute-force, so nothing new is
+    encrypted with it.
     """
     derived = hashlib.sha256(key.encode()).digest()
     return base64.urlsafe_b64encode(derived).decode()
@@ -114,7 +143,7 @@ def _ensure_fernet_key(key: str) -> str:
 
 def get_fernet() -> Fernet:
     """
-    Get Fernet instance with the configured encryption key.
+    Get the legacy (SHA-256-derived) Fernet instance for the configured key.
 
     Returns:
         Fernet instance.
@@ -125,6 +154,42 @@ def get_fernet() -> Fernet:
     return Fernet(_ensure_fernet_key(_get_encryption_key()).encode())
 
 
+@lru_cache(maxsize=4)
+def _derive_master_key(passphrase: str) -> bytes:
+    """Stretch the passphrase with Argon2id. Cached: it is deliberately slow."""
+    kdf = Argon2id(
+        salt=ARGON2_SALT,
+        length=32,
+        iterations=ARGON2_ITERATIONS,
+        lanes=ARGON2_LANES,
+        memory_cost=ARGON2_MEMORY_COST_KIB,
+    )
+    return kdf.derive(passphrase.encode())
+
+
+def prime_key_derivation() -> None:
+    """Derive the Argon2id master key ahead of time if a key is configured.
+
+    The derivation takes a noticeable fraction of a second and its first
+    caller would otherwise be an async handler or worker task decrypting a
+    credential, blocking the event loop. Call at startup (off the loop).
+    """
+    if get_secret_from_env("OPEN_NOTEBOOK_ENCRYPTION_KEY"):
+        _derive_master_key(_get_encryption_key())
+
+
+def _get_v2_fernet(salt: bytes) -> Fernet:
+    """Get the Fernet instance for one v2 value, keyed by HKDF(master, salt)."""
+    hkdf = HKDF(algorithm=hashes.SHA256(), length=32, salt=salt, info=HKDF_INFO)
+    derived = hkdf.derive(_derive_master_key(_get_encryption_key()))
+    return Fernet(base64.urlsafe_b64encode(derived))
+
+
+def needs_reencryption(value: str) -> bool:
+    """Whether a stored value predates the current (v2) key derivation."""
+    return not value.startswith(CIPHERTEXT_V2_PREFIX)
+
+
 def encrypt_value(value: str) -> str:
     """
     Encrypt a string value using Fernet symmetric encryption.
@@ -133,13 +198,32 @@ def encrypt_value(value: str) -> str:
         value: The plain text string to encrypt.
 
     Returns:
-        Base64-encoded encrypted string.
+        Versioned ciphertext: ``v2:<base64 salt>:<Fernet token>``.
 
     Raises:
         ValueError: If encryption is not configured.
     """
-    fernet = get_fernet()
-    return fernet.encrypt(value.encode()).decode()
+    salt = os.urandom(HKDF_SALT_BYTES)
+    token = _get_v2_fernet(salt).encrypt(value.encode()).decode()
+    encoded_salt = base64.urlsafe_b64encode(salt).decode()
+    return f"{CIPHERTEXT_V2_PREFIX}{encoded_salt}:{token}"
+
+
+def _decrypt_v2(value: str) -> str:
+    """Decrypt a ``v2:<salt>:<token>`` value written by ``encrypt_value``."""
+    try:
+        encoded_salt, token = value[len(CIPHERTEXT_V2_PREFIX) :].split(":", 1)
+        salt = base64.urlsafe_b64decode(encoded_salt)
+    except (ValueError, binascii.Error):
+        raise ValueError("Decryption failed: malformed encrypted value")
+
+    try:
+        return _get_v2_fernet(salt).decrypt(token.encode()).decode()
+    except InvalidToken:
+        raise ValueError(
+            "Decryption failed: data appears to be encrypted but key is incorrect. "
+            "Check OPEN_NOTEBOOK_ENCRYPTION_KEY configuration."
+        )
 
 
 def looks_like_fernet_token(s: str) -> bool:
@@ -168,7 +252,8 @@ def decrypt_value(value: str) -> str:
     """
     Decrypt a Fernet-encrypted string value.
 
-    Handles graceful fallback for legacy unencrypted data.
+    Reads both the current ``v2:`` format and legacy SHA-256-keyed tokens,
+    and falls back gracefully for legacy unencrypted data.
 
     Args:
         value: The encrypted string (or plain text for legacy data).
@@ -180,6 +265,9 @@ def decrypt_value(value: str) -> str:
         ValueError: If encryption is not configured or if decryption fails
             for what appears to be encrypted data (wrong key).
     """
+    if value.startswith(CIPHERTEXT_V2_PREFIX):
+        return _decrypt_v2(value)
+
     fernet = get_fernet()
 
     try:
@@ -8,12 +8,14 @@ them into `api/routers/_chat_shared.py`:
 - session/source verification (missing record -> 404, missing `refers_to`
   relation -> 404 on every method now that the routers re-raise HTTPException
   instead of swallowing it into a 500)
-- LangGraph state -> `ChatMessage` extraction shapes (type/content fallbacks)
+- LangGraph state -> `ChatMessage` extraction shapes (type/content fallbacks),
+  including content-part lists in history and in the source-chat SSE stream
 
 DB access and LangGraph state are mocked following the style of
 tests/test_crud_404.py.
 """
 
+import json
 from types import SimpleNamespace
 from unittest.mock import AsyncMock, MagicMock, patch
 
@@ -158,6 +160,52 @@ async def test_get_chat_session_message_shapes(mock_get, mock_graph, mock_repo,
     }
 
 
+@pytest.mark.asyncio
+@patch("api.routers.chat.repo_query", new_callable=AsyncMock)
+@patch("api.routers.chat.chat_graph")
+@patch("api.routers.chat.ChatSession.get", new_callable=AsyncMock)
+async def test_get_chat_session_flattens_content_part_lists(
+    mock_get, mock_graph, mock_repo, client
+):
+    # OpenAI-compatible proxies can return content as a list of parts; older
+    # checkpoints may still hold that raw shape.
+    parts = [
+        {"type": "text", "text": "Hello "},
+        {"type": "reasoning", "reasoning": "ignored"},
+        "world",
+    ]
+    mock_get.return_value = _session()
+    mock_graph.get_state.return_value = _graph_state(
+        {"messages": [_Msg("m1", "ai", parts)]}
+    )
+    mock_repo.return_value = []
+
+    resp = client.get("/api/chat/sessions/abc")
+
+    assert resp.status_code == 200
+    assert resp.json()["messages"][0]["content"] == "Hello world"
+
+
+@pytest.mark.asyncio
+@patch("api.routers.source_chat.source_chat_graph")
+async def test_source_chat_stream_flattens_content_part_lists(mock_graph):
+    from api.routers.source_chat import stream_source_chat_response
+
+    parts = [{"type": "text", "text": "Hello "}, "world"]
+    mock_graph.get_state.return_value = _graph_state({"messages": []})
+    mock_graph.invoke.return_value = {"messages": [_Msg("m1", "ai", parts)]}
+
+    events = [
+        json.loads(chunk.removeprefix("data: "))
+        async for chunk in stream_source_chat_response(
+            "source_chat_session:abc", "source:xyz", "hi"
+        )
+    ]
+
+    ai_events = [e for e in events if e["type"] == "ai_message"]
+    assert [e["content"] for e in ai_events] == ["Hello world"]
+
+
 @pytest.mark.asyncio
 @patch("api.routers.chat.repo_query", new_callable=AsyncMock)
 @patch("api.routers.chat.chat_graph")
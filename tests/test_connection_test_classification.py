@@ -8,9 +8,16 @@ Two semantics share one auth/network classifier:
   misreported as a broken connection.
 - The *individual model* test validates one specific registered model, so a
   missing model there IS a failure.
+
+The individual model test also reports the reply as text even when a
+provider returns content as a list of parts.
 """
 
+from unittest.mock import AsyncMock, MagicMock, patch
+
 import pytest
+from esperanto import LanguageModel
+from esperanto.common_types import ChatCompletion
 
 from open_notebook.ai.connection_tester import (
     _connection_failure_reason,
@@ -18,6 +25,9 @@ from open_notebook.ai.connection_tester import (
     _normalize_error_message,
     classify_provider_test_error,
 )
+from open_notebook.ai.connection_tester import (
+    test_individual_model as run_individual_model_test,
+)
 
 # Realistic provider error strings.
 GOOGLE_RETIRED_MODEL_404 = (
@@ -135,3 +145,25 @@ class TestIndividualModelSemanticsDiffer:
         success, message = _normalize_error_message(GOOGLE_BAD_KEY_401)
         assert success is False
         assert "Invalid API key" in message
+
+
+class TestIndividualModelResponseText:
+    @pytest.mark.asyncio
+    async def test_content_part_list_is_flattened(self):
+        # OpenAI-compatible proxies can return content as a list of parts
+        model = MagicMock(id="model:proxy1", provider="openai", type="language")
+        response = MagicMock(spec=ChatCompletion)
+        response.content = [{"type": "text", "text": "Hello"}, " there"]
+        esp_model = MagicMock(spec=LanguageModel)
+        esp_model.achat_complete = AsyncMock(return_value=response)
+
+        manager_instance = MagicMock()
+        manager_instance.get_model = AsyncMock(return_value=esp_model)
+
+        with patch(
+            "open_notebook.ai.models.ModelManager", return_value=manager_instance
+        ):
+            success, message = await run_individual_model_test(model)
+
+        assert success is True
+        assert message == "Response: Hello there"
@@ -0,0 +1,172 @@
+"""
+Tests for the versioned key derivation in open_notebook/utils/encryption.py.
+
+The Fernet key used to be a single SHA-256 of OPEN_NOTEBOOK_ENCRYPTION_KEY.
+New values are written as `v2:<salt>:<token>` with an Argon2id-stretched
+master key and a per-value HKDF key; legacy tokens and legacy plaintext
+must keep decrypting, and Credential/ProviderConfig.reencrypt_legacy_api_keys()
+upgrade stored keys in place.
+"""
+
+from unittest.mock import AsyncMock, patch
+
+import pytest
+
+from open_notebook.domain.credential import Credential
+from open_notebook.domain.provider_config import ProviderConfig
+from open_notebook.utils import encryption
+from open_notebook.utils.encryption import (
+    CIPHERTEXT_V2_PREFIX,
+    decrypt_value,
+    encrypt_value,
+    get_fernet,
+    needs_reencryption,
+    prime_key_derivation,
+)
+
+
+@pytest.fixture(autouse=True)
+def encryption_key(monkeypatch):
+    monkeypatch.setattr(encryption, "_ENCRYPTION_KEY", "test-passphrase")
+
+
+def legacy_encrypt(value: str) -> str:
+    return get_fernet().encrypt(value.encode()).decode()
+
+
+class TestV2Format:
+    def test_round_trip(self):
+        encrypted = encrypt_value("sk-secret")
+        assert encrypted.startswith(CIPHERTEXT_V2_PREFIX)
+        assert decrypt_value(encrypted) == "sk-secret"
+
+    def test_same_value_encrypts_differently(self):
+        # Random per-value HKDF salt.
+        assert encrypt_value("sk-secret") != encrypt_value("sk-secret")
+
+    def test_wrong_key_raises(self, monkeypatch):
+        encrypted = encrypt_value("sk-secret")
+        monkeypatch.setattr(encryption, "_ENCRYPTION_KEY", "another-passphrase")
+        with pytest.raises(ValueError, match="key is incorrect"):
+            decrypt_value(encrypted)
+
+    def test_malformed_value_raises(self):
+        with pytest.raises(ValueError, match="malformed"):
+            decrypt_value(f"{CIPHERTEXT_V2_PREFIX}no-separator")
+
+
+class TestLegacyCompatibility:
+    def test_legacy_token_still_decrypts(self):
+        assert decrypt_value(legacy_encrypt("sk-legacy")) == "sk-legacy"
+
+    def test_legacy_plaintext_passes_through(self):
+        assert decrypt_value("sk-plaintext") == "sk-plaintext"
+
+    def test_needs_reencryption(self):
+        assert needs_reencryption(legacy_encrypt("sk-legacy")) is True
+        assert needs_reencryption("sk-plaintext") is True
+        assert needs_reencryption(encrypt_value("sk-new")) is False
+
+
+class TestPrimeKeyDerivation:
+    def test_caches_master_key_when_key_configured(self, monkeypatch):
+        monkeypatch.setenv("OPEN_NOTEBOOK_ENCRYPTION_KEY", "test-passphrase")
+        encryption._derive_master_key.cache_clear()
+
+        prime_key_derivation()
+
+        assert encryption._derive_master_key.cache_info().currsize == 1
+        encrypt_value("sk-secret")
+        assert encryption._derive_master_key.cache_info().hits == 1
+
+    def test_noop_without_key(self, monkeypatch):
+        monkeypatch.delenv("OPEN_NOTEBOOK_ENCRYPTION_KEY", raising=False)
+        monkeypatch.delenv("OPEN_NOTEBOOK_ENCRYPTION_KEY_FILE", raising=False)
+        encryption._derive_master_key.cache_clear()
+
+        prime_key_derivation()
+
+        assert encryption._derive_master_key.cache_info().currsize == 0
+
+
+class TestReencryptLegacyApiKeys:
+    @pytest.mark.asyncio
+    async def test_upgrades_only_legacy_rows(self, monkeypatch):
+        legacy = legacy_encrypt("sk-legacy")
+        current = encrypt_value("sk-current")
+        monkeypatch.setattr(encryption, "_ENCRYPTION_KEY", "old-passphrase")
+        undecryptable = legacy_encrypt("sk-lost")
+        monkeypatch.setattr(encryption, "_ENCRYPTION_KEY", "test-passphrase")
+
+        rows = [
+            {"id": "credential:legacy", "api_key": legacy},
+            {"id": "credential:current", "api_key": current},
+            {"id": "credential:lost", "api_key": undecryptable},
+            {"id": "credential:none", "api_key": None},
+        ]
+        mock_query = AsyncMock(side_effect=[rows, []])
+
+        with patch("open_notebook.domain.credential.repo_query", new=mock_query):
+            upgraded = await Credential.reencrypt_legacy_api_keys()
+
+        assert upgraded == 1
+        assert mock_query.await_count == 2
+        update_query, params = mock_query.await_args_list[1].args
+        assert update_query.startswith("UPDATE")
+        assert params["id"].table_name == "credential"
+        assert params["id"].id == "legacy"
+        assert params["api_key"].startswith(CIPHERTEXT_V2_PREFIX)
+        assert decrypt_value(params["api_key"]) == "sk-legacy"
+
+
+class TestReencryptLegacyProviderConfig:
+    @pytest.mark.asyncio
+    async def test_upgrades_legacy_keys_in_record(self):
+        current = encrypt_value("sk-current")
+        record = {
+            "id": "open_notebook:provider_configs",
+            "credentials": {
+                "openai": [
+                    {"id": "a", "name": "Default", "api_key": legacy_encrypt("sk-a")},
+                    {"id": "b", "name": "Prod", "api_key": current},
+                ],
+                "ollama": [{"id": "c", "name": "Local", "api_key": None}],
+            },
+        }
+        mock_query = AsyncMock(side_effect=[[record], []])
+
+        with patch("open_notebook.domain.provider_config.repo_query", new=mock_query):
+            upgraded = await ProviderConfig.reencrypt_legacy_api_keys()
+
+        assert upgraded == 1
+        update_query, params = mock_query.await_args_list[1].args
+        assert update_query.startswith("UPDATE")
+        openai = params["credentials"]["openai"]
+        assert openai[0]["api_key"].startswith(CIPHERTEXT_V2_PREFIX)
+        assert decrypt_value(openai[0]["api_key"]) == "sk-a"
+        assert openai[1]["api_key"] == current
+        assert params["credentials"]["ollama"][0]["api_key"] is None
+
+    @pytest.mark.asyncio
+    async def test_no_update_without_legacy_keys(self):
+        record = {
+            "credentials": {
+                "openai": [
+                    {"id": "a", "name": "Default", "api_key": encrypt_value("sk")}
+                ]
+            }
+        }
+        mock_query = AsyncMock(side_effect=[[record]])
+
+        with patch("open_notebook.domain.provider_config.repo_query", new=mock_query):
+            upgraded = await ProviderConfig.reencrypt_legacy_api_keys()
+
+        assert upgraded == 0
+        assert mock_query.await_count == 1
+
+    @pytest.mark.asyncio
+    async def test_missing_record_is_a_noop(self):
+        mock_query = AsyncMock(return_value=[])
+
+        with patch("open_notebook.domain.provider_config.repo_query", new=mock_query):
+            assert await ProviderConfig.reencrypt_legacy_api_keys() == 0
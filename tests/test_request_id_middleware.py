@@ -0,0 +1,131 @@
+"""
+Tests for api.middleware.RequestIdMiddleware.
+
+Every HTTP response - errors included - carries an `X-Request-Id` that is
+either the well-formed id the client/proxy sent or a generated one, and
+everything logged while handling the request is prefixed with that id so a
+reported id can be grepped in the logs.
+"""
+
+import re
+
+import pytest
+from fastapi import FastAPI, HTTPException, Request
+from fastapi.testclient import TestClient
+from loguru import logger
+
+from api.middleware import RequestIdMiddleware, add_request_id_to_log_record
+
+GENERATED_ID = re.compile(r"^[0-9a-f]{32}$")
+
+
+@pytest.fixture
+def client():
+    app = FastAPI()
+
+    @app.get("/ok")
+    async def ok(request: Request):
+        logger.info("handling ok")
+        return {"request_id": request.state.request_id}
+
+    @app.get("/fail")
+    async def fail():
+        raise HTTPException(status_code=500, detail="boom")
+
+    @app.get("/crash")
+    async def crash():
+        raise RuntimeError("unhandled")
+
+    app.add_middleware(RequestIdMiddleware)
+    return TestClient(app, raise_server_exceptions=False)
+
+
+class TestRequestIdHeader:
+    def test_generates_id_when_absent(self, client):
+        response = client.get("/ok")
+
+        request_id = response.headers["x-request-id"]
+        assert GENERATED_ID.match(request_id)
+        assert response.json() == {"request_id": request_id}
+
+    def test_reuses_well_formed_incoming_id(self, client):
+        response = client.get("/ok", headers={"X-Request-Id": "proxy-abc.123"})
+
+        assert response.headers["x-request-id"] == "proxy-abc.123"
+        assert response.json() == {"request_id": "proxy-abc.123"}
+
+    @pytest.mark.parametrize("incoming", ["has space", "x" * 129, "a\tb"])
+    def test_replaces_malformed_incoming_id(self, client, incoming):
+        response = client.get("/ok", headers={"X-Request-Id": incoming})
+
+        assert GENERATED_ID.match(response.headers["x-request-id"])
+
+    def test_error_responses_carry_id(self, client):
+        response = client.get("/fail", headers={"X-Request-Id": "failing-req"})
+
+        assert response.status_code == 500
+        assert response.headers["x-request-id"] == "failing-req"
+
+    def test_unhandled_exceptions_carry_id(self, client):
+        response = client.get("/crash", headers={"X-Request-Id": "crashing-req"})
+
+        assert response.status_code == 500
+        assert response.headers["x-request-id"] == "crashing-req"
+        assert response.json() == {"detail": "Internal Server Error"}
+
+    def test_unhandled_error_500_gets_error_headers(self):
+        app = FastAPI()
+
+        @app.get("/crash")
+        async def crash():
+            raise RuntimeError("unhandled")
+
+        app.add_middleware(
+            RequestIdMiddleware,
+            error_headers=lambda scope: {
+                "Access-Control-Allow-Origin": "http://frontend.example"
+            },
+        )
+        response = TestClient(app, raise_server_exceptions=False).get("/crash")
+
+        assert response.status_code == 500
+        assert (
+            response.headers["access-control-allow-origin"]
+            == "http://frontend.example"
+        )
+        assert GENERATED_ID.match(response.headers["x-request-id"])
+
+    def test_registered_on_real_app(self):
+        from api.main import app
+
+        response = TestClient(app).get("/health")
+
+        assert GENERATED_ID.match(response.headers["x-request-id"])
+
+
+class TestLogCorrelation:
+    def test_log_lines_are_prefixed_with_request_id(self, client):
+        messages: list[str] = []
+        logger.configure(patcher=add_request_id_to_log_record)
+        sink_id = logger.add(lambda m: messages.append(m.record["message"]))
+        try:
+            client.get("/ok", headers={"X-Request-Id": "grep-me"})
+            logger.info("outside any request")
+        finally:
+            logger.remove(sink_id)
+
+        assert "[grep-me] handling ok" in messages
+        assert "outside any request" in messages
+
+    def test_unhandled_error_traceback_is_logged_with_request_id(self, client):
+        records = []
+        logger.configure(patcher=add_request_id_to_log_record)
+        sink_id = logger.add(lambda m: records.append(m.record), level="ERROR")
+        try:
+            client.get("/crash", headers={"X-Request-Id": "crash-trace"})
+        finally:
+            logger.remove(sink_id)
+
+        (record,) = records
+        assert record["message"] == "[crash-trace] Unhandled error in GET /crash"
+        assert record["exception"] is not None
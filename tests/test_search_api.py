@@ -41,6 +41,36 @@ class TestSearchLimitValidation:
         mock_text_search.assert_awaited_once()
 
 
+class TestSearchInputLengthValidation:
+    """Search queries and ask questions are capped at 10,000 characters, so a
+    pasted document can't be sent through embedding/full-text search or the
+    ask strategy model."""
+
+    def test_query_at_max_length_is_accepted(self):
+        from api.models import SearchRequest
+
+        assert len(SearchRequest(query="x" * 10000).query) == 10000
+
+    def test_query_above_max_length_returns_422(self, client):
+        response = client.post(
+            "/api/search",
+            json={"query": "x" * 10001, "type": "text"},
+        )
+        assert response.status_code == 422
+
+    def test_question_above_max_length_returns_422(self, client):
+        response = client.post(
+            "/api/search/ask/simple",
+            json={
+                "question": "x" * 10001,
+                "strategy_model": "model:a",
+                "answer_model": "model:b",
+                "final_answer_model": "model:c",
+            },
+        )
+        assert response.status_code == 422
+
+
 class TestTextSearchHighlightOverflowFallback:
     """text_search() must fall back to vector search on a highlight position overflow (#648)."""
 
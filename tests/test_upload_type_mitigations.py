@@ -3,10 +3,12 @@ Regression tests documenting why the "no file type/content allowlist on
 uploads" finding is low-risk without adding one.
 
 Investigated adding an extension/MIME allowlist and decided against it:
-- Downloads are always served as application/octet-stream regardless of
-  the uploaded file's actual type (locked in below) - the classic "upload
-  HTML/SVG, victim opens it, browser renders/executes it" vector is closed
-  independent of any allowlist.
+- Downloads are served as application/octet-stream attachments unless the
+  type is one a browser displays without running script (PDF, raster
+  images, plain text, audio/video - only those may be shown with
+  ?inline=true), regardless of what was uploaded (locked in below) - the
+  classic "upload HTML/SVG, victim opens it, browser renders/executes it"
+  vector is closed independent of any allowlist.
 - Uploaded files are never executed server-side; content-core only reads
   and extracts text/media from them.
 - content_core.content.identification.file_detector.FileDetector already
@@ -28,6 +30,7 @@ from unittest.mock import AsyncMock, patch
 import pytest
 from fastapi.testclient import TestClient
 
+from api.routers.sources import _source_file_disposition
 from open_notebook.domain.notebook import Asset, Source
 
 
@@ -44,7 +47,27 @@ def client():
     return TestClient(app)
 
 
-class TestDownloadsAlwaysServedAsOctetStream:
+@pytest.fixture
+def download(client, tmp_path, monkeypatch):
+    """Store `content` as `filename` in a temporary uploads folder and GET it
+    through the source download endpoint."""
+    uploads = tmp_path / "uploads"
+    uploads.mkdir()
+    monkeypatch.setattr("api.routers.sources.UPLOADS_FOLDER", str(uploads))
+
+    def _download(filename, content, **kwargs):
+        file_path = uploads / filename
+        file_path.write_bytes(content)
+        source = make_source(file_path=str(file_path))
+        with patch(
+            "api.routers.sources.Source.get", new=AsyncMock(return_value=source)
+        ):
+            return client.get("/api/sources/source:test123/download", **kwargs)
+
+    return _download
+
+
+class TestNonInlineSafeTypesServedAsOctetStream:
     def test_html_file_download_is_octet_stream_not_text_html(self, client, tmp_path, monkeypatch):
         """Even if an attacker got an .html file stored, downloading it
         must never come back as text/html (which a browser would render)."""
@@ -79,6 +102,78 @@ class TestDownloadsAlwaysServedAsOctetStream:
         assert response.status_code == 200
         assert response.headers["content-type"] == "application/octet-stream"
 
+    def test_html_requested_inline_is_still_octet_stream_attachment(self, download):
+        response = download(
+            "page.html", b"<script>alert(1)</script>", params={"inline": "true"}
+        )
+
+        assert response.status_code == 200
+        assert response.headers["content-type"] == "application/octet-stream"
+        assert response.headers["content-disposition"].startswith("attachment")
+
+
+class TestSourceFileDisposition:
+    def test_pdf_inline_when_requested(self):
+        assert _source_file_disposition("paper.pdf", inline=True) == (
+            "application/pdf",
+            "inline",
+        )
+
+    def test_pdf_attachment_by_default(self):
+        assert _source_file_disposition("paper.pdf", inline=False) == (
+            "application/pdf",
+            "attachment",
+        )
+
+    def test_audio_inline_when_requested(self):
+        media_type, disposition = _source_file_disposition("talk.mp3", inline=True)
+        assert media_type.startswith("audio/")
+        assert disposition == "inline"
+
+    def test_html_never_inline(self):
+        assert _source_file_disposition("page.html", inline=True) == (
+            "application/octet-stream",
+            "attachment",
+        )
+
+    def test_svg_never_inline(self):
+        assert _source_file_disposition("diagram.svg", inline=True) == (
+            "application/octet-stream",
+            "attachment",
+        )
+
+    def test_unknown_extension_falls_back_to_octet_stream(self):
+        assert _source_file_disposition("blob.unknownext", inline=True) == (
+            "application/octet-stream",
+            "attachment",
+        )
+
+
+class TestInlineSafeDownloads:
+    """Types a browser displays without running script keep their media type,
+    may be shown inline, and support Range requests for PDF/media viewers."""
+
+    def test_pdf_download_is_attachment_with_detected_type(self, download):
+        response = download("paper.pdf", b"%PDF-1.4 fake")
+
+        assert response.status_code == 200
+        assert response.headers["content-type"] == "application/pdf"
+        assert response.headers["content-disposition"].startswith("attachment")
+        assert response.headers["x-content-type-options"] == "nosniff"
+
+    def test_pdf_inline(self, download):
+        response = download("paper.pdf", b"%PDF-1.4 fake", params={"inline": "true"})
+
+        assert response.status_code == 200
+        assert response.headers["content-disposition"].startswith("inline")
+
+    def test_range_request_returns_partial_content(self, download):
+        response = download("paper.pdf", b"0123456789", headers={"Range": "bytes=2-5"})
+
+        assert response.status_code == 206
+        assert response.content == b"2345"
+        assert response.headers["content-range"] == "bytes 2-5/10"
+
 
 class TestContentCoreRejectsUnrecognizedContent:
     @pytest.mark.asyncio